@@ -1,5 +1,8 @@
 use core::{ops::BitOr, fmt, convert::TryFrom};
-use prost_types::field_descriptor_proto::Type;
+use proc_macro2::TokenStream;
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::FieldDescriptorProto;
+use syn::Attribute;
 
 macro_rules! implement_conversions {
     ($filter:ident, $selector:ident { $($var:ident,)* }) => {
@@ -81,6 +84,19 @@ pub enum TypeSelector {
     RustEnum          = 1<<4,
     RustEnumCLike     = 1<<5,
     RustEnumWithData  = 1<<6,
+
+    /// A generated struct whose message is trivially copyable: every
+    /// field is a scalar varint/fixed/bool/enum, with no `message`,
+    /// `string`, `bytes`, repeated, map, or boxed-optional field, and
+    /// (recursively) every oneof variant satisfies the same rule. See
+    /// `message_is_copyable`.
+    CopyableStruct    = 1<<7,
+
+    /// A `google.protobuf.*` well-known type (`Timestamp`, `Duration`,
+    /// `Any`, `Struct`, the wrapper types, etc). See
+    /// `is_well_known_type_full`.
+    WellKnownType     = 1<<8,
+
     Everything        = u32::MAX,
 }
 
@@ -94,9 +110,20 @@ implement_conversions!(
         RustEnum,
         RustEnumCLike,
         RustEnumWithData,
+        CopyableStruct,
+        WellKnownType,
     }
 );
 
+impl TypeFilter {
+    /// Whether this filter matches an object characterized by
+    /// `object_bits` (the `TypeSelector` bits describing it, e.g.
+    /// `ProtobufMessage | RustStruct`), mirroring `FieldFilter::matches`.
+    pub(crate) fn matches(&self, object_bits: TypeFilter) -> bool {
+        self.0 & object_bits.0 != 0
+    }
+}
+
 macro_rules! impl_from_type {
     ($selector:ident { $($var:ident,)* }) => {
         impl From<Type> for $selector {
@@ -144,6 +171,28 @@ pub enum FieldSelector {
 
     /// map field
     MapField = 1<<21,
+
+    /// `repeated` field (includes map fields, which are repeated under the hood)
+    Repeated = 1<<22,
+
+    /// repeated field that is wire-packed
+    Packed = 1<<23,
+
+    /// singular field that tracks presence explicitly, i.e. lowers to
+    /// `Option<T>`: a proto2 `optional` field, or a proto3 field marked
+    /// `optional`. Does not include `required` fields, see `Required`.
+    ExplicitPresence = 1<<24,
+
+    /// `required` field (proto2 only)
+    Required = 1<<25,
+
+    /// singular (non-repeated, non-map) field
+    Singular = 1<<26,
+
+    /// message-typed field whose target is a `google.protobuf.*`
+    /// well-known type
+    WellKnownTypeField = 1<<27,
+
     Everything = u32::MAX,
 }
 
@@ -155,9 +204,208 @@ implement_conversions!(
         Uint32, Enum, Sfixed32, Sfixed64, Sint32, Sint64,
 
         NoDataEnumVariant, OneofField, MapField,
+        Repeated, Packed, ExplicitPresence, Required, Singular,
+        WellKnownTypeField,
     }
 );
 
+/// The proto syntax a `.proto` file was declared with. Some field
+/// properties (e.g. whether a singular field has explicit presence)
+/// depend on this in addition to the field's `Label`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Syntax {
+    Proto2,
+    Proto3,
+}
+
+/// A field descriptor together with the context needed to evaluate every
+/// `FieldSelector` against it: the cardinality/presence bits (`Repeated`,
+/// `Packed`, `ExplicitPresence`, `Required`, `Singular`) depend on the
+/// field's `Label` and the enclosing file's `Syntax`, and `OneofField`/
+/// `MapField` depend on facts that aren't recoverable from the
+/// `FieldDescriptorProto` alone (oneof membership is known to the caller
+/// walking the message's fields; map-entry-ness lives on the *target*
+/// message's `MessageOptions.map_entry`, not on this field), so the caller
+/// threads both in explicitly, same as `syntax`.
+pub(crate) struct FieldWithContext<'a> {
+    descriptor: &'a FieldDescriptorProto,
+    syntax: Syntax,
+    is_oneof_member: bool,
+    is_map_entry: bool,
+}
+
+impl<'a> FieldWithContext<'a> {
+    pub(crate) fn new(
+        descriptor: &'a FieldDescriptorProto,
+        syntax: Syntax,
+        is_oneof_member: bool,
+        is_map_entry: bool,
+    ) -> Self {
+        FieldWithContext {
+            descriptor,
+            syntax,
+            is_oneof_member,
+            is_map_entry,
+        }
+    }
+
+    fn is_repeated(&self) -> bool {
+        self.descriptor.label() == Label::Repeated
+    }
+
+    fn is_required(&self) -> bool {
+        self.descriptor.label() == Label::Required
+    }
+
+    fn is_packed(&self) -> bool {
+        if !self.is_repeated() || !Self::is_packable(self.descriptor.r#type()) {
+            return false;
+        }
+        match self.descriptor.options.as_ref().and_then(|options| options.packed) {
+            Some(packed) => packed,
+            // proto3 packs packable repeated scalar fields by default
+            None => self.syntax == Syntax::Proto3,
+        }
+    }
+
+    fn has_explicit_presence(&self) -> bool {
+        if self.is_repeated() {
+            return false;
+        }
+        match self.syntax {
+            Syntax::Proto2 => self.descriptor.label() == Label::Optional,
+            Syntax::Proto3 => self.descriptor.proto3_optional(),
+        }
+    }
+
+    fn is_singular(&self) -> bool {
+        !self.is_repeated()
+    }
+
+    fn is_packable(ty: Type) -> bool {
+        !matches!(
+            ty,
+            Type::String | Type::Bytes | Type::Message | Type::Group
+        )
+    }
+
+    /// The full set of `FieldSelector` bits that apply to this field: its
+    /// wire type, `OneofField`/`MapField` as threaded in by the caller, and
+    /// whichever cardinality/presence bits its `Label` and `Syntax` resolve
+    /// to. `FieldFilter::matches` and everything built on it go through
+    /// this, so every `FieldSelector` variant must be reflected here.
+    pub(crate) fn selector_bits(&self) -> u32 {
+        let mut bits = FieldSelector::from(self.descriptor.r#type()) as u32;
+        if self.is_oneof_member {
+            bits |= FieldSelector::OneofField as u32;
+        }
+        if self.is_map_entry {
+            bits |= FieldSelector::MapField as u32;
+        }
+        if self.is_repeated() {
+            bits |= FieldSelector::Repeated as u32;
+        }
+        if self.is_packed() {
+            bits |= FieldSelector::Packed as u32;
+        }
+        if self.has_explicit_presence() {
+            bits |= FieldSelector::ExplicitPresence as u32;
+        }
+        if self.is_required() {
+            bits |= FieldSelector::Required as u32;
+        }
+        if self.is_singular() {
+            bits |= FieldSelector::Singular as u32;
+        }
+        if self.is_well_known_type_field() {
+            bits |= FieldSelector::WellKnownTypeField as u32;
+        }
+        bits
+    }
+
+    /// Whether this field's target type (for message-typed fields) is a
+    /// `google.protobuf.*` well-known type, backing
+    /// `FieldSelector::WellKnownTypeField`.
+    fn is_well_known_type_field(&self) -> bool {
+        self.descriptor.r#type() == Type::Message
+            && self
+                .descriptor
+                .type_name
+                .as_deref()
+                .is_some_and(is_well_known_type_full)
+    }
+
+    /// Whether this field, taken on its own, is compatible with its
+    /// enclosing struct deriving `Copy`. Does not account for oneofs;
+    /// see `message_is_copyable` for the full message-level check.
+    fn is_copy_candidate(&self, is_enum_copy: &dyn Fn(&str) -> bool) -> bool {
+        if self.is_repeated() {
+            return false;
+        }
+        match self.descriptor.r#type() {
+            Type::Message | Type::String | Type::Bytes | Type::Group => false,
+            Type::Enum => self
+                .descriptor
+                .type_name
+                .as_deref()
+                .is_some_and(is_enum_copy),
+            _ => true,
+        }
+    }
+}
+
+/// The fully-qualified (leading-dot) names of the `google.protobuf.*`
+/// well-known types, as produced by `FileDescriptorProto`/
+/// `FieldDescriptorProto::type_name`.
+const WELL_KNOWN_TYPES: &[&str] = &[
+    ".google.protobuf.Any",
+    ".google.protobuf.Api",
+    ".google.protobuf.BoolValue",
+    ".google.protobuf.BytesValue",
+    ".google.protobuf.DoubleValue",
+    ".google.protobuf.Duration",
+    ".google.protobuf.Empty",
+    ".google.protobuf.Enum",
+    ".google.protobuf.EnumValue",
+    ".google.protobuf.Field",
+    ".google.protobuf.FieldMask",
+    ".google.protobuf.FloatValue",
+    ".google.protobuf.Int32Value",
+    ".google.protobuf.Int64Value",
+    ".google.protobuf.ListValue",
+    ".google.protobuf.Method",
+    ".google.protobuf.Mixin",
+    ".google.protobuf.NullValue",
+    ".google.protobuf.Option",
+    ".google.protobuf.SourceContext",
+    ".google.protobuf.StringValue",
+    ".google.protobuf.Struct",
+    ".google.protobuf.Syntax",
+    ".google.protobuf.Timestamp",
+    ".google.protobuf.Type",
+    ".google.protobuf.UInt32Value",
+    ".google.protobuf.UInt64Value",
+    ".google.protobuf.Value",
+];
+
+/// Whether `full_name` (a fully-qualified, leading-dot protobuf type name,
+/// e.g. `.google.protobuf.Timestamp`) names one of the `google.protobuf.*`
+/// well-known types. Backs both `TypeSelector::WellKnownType` (resolved
+/// from a message's own full name) and `FieldSelector::WellKnownTypeField`
+/// (resolved from a message-typed field's target).
+pub(crate) fn is_well_known_type_full(full_name: &str) -> bool {
+    WELL_KNOWN_TYPES.contains(&full_name)
+}
+
+impl FieldFilter {
+    /// Whether this filter matches the given field, i.e. whether any of
+    /// the `FieldSelector`s it was built from apply to `field` once its
+    /// `Label` and `Syntax` have been resolved.
+    pub(crate) fn matches(&self, field: &FieldWithContext<'_>) -> bool {
+        self.0 & field.selector_bits() != 0
+    }
+}
+
 impl_from_type!(
     FieldSelector {
         Double, Float, Int64, Uint64, Int32, Fixed64,
@@ -165,3 +413,440 @@ impl_from_type!(
         Uint32, Enum, Sfixed32, Sfixed64, Sint32, Sint64,
     }
 );
+
+/// Whether a message is a candidate for auto-deriving `Copy` (backing
+/// `TypeSelector::CopyableStruct`): every field must be a scalar
+/// varint/fixed/bool/enum field, with no message/string/bytes payload and
+/// no repetition.
+///
+/// `fields` must be the message's fully-resolved field set, i.e. with
+/// oneofs already expanded to their member fields (a oneof-bearing
+/// message is only copyable if every one of its variants is). `is_enum_copy`
+/// resolves whether a field's referenced enum type is itself C-like, since
+/// only C-like enums are `Copy`.
+pub(crate) fn message_is_copyable<'a>(
+    fields: impl IntoIterator<Item = &'a FieldWithContext<'a>>,
+    is_enum_copy: &dyn Fn(&str) -> bool,
+) -> bool {
+    fields
+        .into_iter()
+        .all(|field| field.is_copy_candidate(is_enum_copy))
+}
+
+/// Hints which (de)serialization codec a substituted field type needs, so
+/// a field whose Rust type was overridden by `Config::substitute_field_type`
+/// still round-trips as standard protobuf wire data even though its Rust
+/// representation no longer matches prost's default mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodecHint {
+    /// Encode/decode like a length-delimited byte buffer (`bytes::Bytes`
+    /// and friends).
+    Bytes,
+    /// Encode/decode like a length-delimited UTF-8 string.
+    String,
+}
+
+/// One registered field-type substitution: the Rust type `target` for
+/// fields matched by `filter`, in place of prost's default mapping, and
+/// the `codec` whose read/write expressions keep them wire-compatible.
+pub struct FieldTypeOverride {
+    filter: FieldFilter,
+    target: TokenStream,
+    codec: CodecHint,
+}
+
+impl FieldTypeOverride {
+    /// The Rust type to emit for matching fields.
+    pub(crate) fn target(&self) -> &TokenStream {
+        &self.target
+    }
+
+    /// The codec to use when generating encode/decode expressions for
+    /// matching fields.
+    pub(crate) fn codec(&self) -> CodecHint {
+        self.codec
+    }
+}
+
+/// Ordered collection of `FieldTypeOverride`s, populated by
+/// `Config::substitute_field_type`; for a given field, `lookup` returns
+/// the first-registered matching override. No code generator in this
+/// crate calls `lookup`, so these overrides do not affect generated output.
+#[derive(Default)]
+pub(crate) struct FieldTypeSubstitutions(Vec<FieldTypeOverride>);
+
+impl FieldTypeSubstitutions {
+    pub(crate) fn register(&mut self, filter: FieldFilter, target: TokenStream, codec: CodecHint) {
+        self.0.push(FieldTypeOverride {
+            filter,
+            target,
+            codec,
+        });
+    }
+
+    /// The first registered override whose filter matches `field`, if any.
+    pub(crate) fn lookup(&self, field: &FieldWithContext<'_>) -> Option<&FieldTypeOverride> {
+        self.0.iter().find(|o| o.filter.matches(field))
+    }
+}
+
+/// Context passed to a registered type rule's closure: the protobuf name
+/// and resolved Rust identifier of the struct/enum it matched, plus which
+/// `TypeSelector` bits of the filter actually matched it.
+pub struct TypeContext<'a> {
+    pub proto_name: &'a str,
+    pub rust_ident: &'a syn::Ident,
+    pub matched: TypeFilter,
+}
+
+/// Context passed to a registered field rule's closure, mirroring
+/// `TypeContext` for an individual field.
+pub struct FieldContext<'a> {
+    pub proto_name: &'a str,
+    pub rust_ident: &'a syn::Ident,
+    pub matched: FieldFilter,
+}
+
+struct TypeRule {
+    filter: TypeFilter,
+    attrs: Box<dyn Fn(&TypeContext<'_>) -> Vec<Attribute>>,
+}
+
+struct FieldRule {
+    filter: FieldFilter,
+    attrs: Box<dyn Fn(&FieldContext<'_>) -> Vec<Attribute>>,
+}
+
+/// A registry binding `TypeFilter`/`FieldFilter` rules to attribute and
+/// derive injection. Rules are evaluated in registration order; every
+/// matching rule's attributes are appended, so later rules never shadow
+/// earlier ones.
+///
+/// This is the registry itself; no message/enum code generator in this
+/// crate calls `type_attrs`/`field_attrs` yet, so registering rules here
+/// doesn't yet affect generated output. Wiring those call sites into the
+/// message and enum generators is follow-up work, not part of this
+/// commit.
+#[derive(Default)]
+pub struct Customizer {
+    type_rules: Vec<TypeRule>,
+    field_rules: Vec<FieldRule>,
+}
+
+impl Customizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a rule that appends `attrs(ctx)` to every type matched by
+    /// `filter`.
+    pub fn on_type<F>(&mut self, filter: impl Into<TypeFilter>, attrs: F) -> &mut Self
+    where
+        F: Fn(&TypeContext<'_>) -> Vec<Attribute> + 'static,
+    {
+        self.type_rules.push(TypeRule {
+            filter: filter.into(),
+            attrs: Box::new(attrs),
+        });
+        self
+    }
+
+    /// Register a rule that appends `attrs(ctx)` to every field matched by
+    /// `filter`.
+    pub fn on_field<F>(&mut self, filter: impl Into<FieldFilter>, attrs: F) -> &mut Self
+    where
+        F: Fn(&FieldContext<'_>) -> Vec<Attribute> + 'static,
+    {
+        self.field_rules.push(FieldRule {
+            filter: filter.into(),
+            attrs: Box::new(attrs),
+        });
+        self
+    }
+
+    /// All attributes to attach to a type, gathered by evaluating every
+    /// registered type rule against `object_bits` (the `TypeSelector` bits
+    /// describing the object, e.g. `ProtobufMessage | RustStruct`) in
+    /// registration order and appending each match's attributes.
+    pub(crate) fn type_attrs(&self, object_bits: TypeFilter, ctx: &TypeContext<'_>) -> Vec<Attribute> {
+        let mut out = Vec::new();
+        for rule in &self.type_rules {
+            if rule.filter.matches(object_bits) {
+                out.extend((rule.attrs)(ctx));
+            }
+        }
+        out
+    }
+
+    /// All attributes to attach to a field, gathered by evaluating every
+    /// registered field rule against `field` in registration order and
+    /// appending each match's attributes.
+    pub(crate) fn field_attrs(&self, field: &FieldWithContext<'_>, ctx: &FieldContext<'_>) -> Vec<Attribute> {
+        let mut out = Vec::new();
+        for rule in &self.field_rules {
+            if rule.filter.matches(field) {
+                out.extend((rule.attrs)(ctx));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_types::FieldOptions;
+
+    fn field(label: Label, ty: Type) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            label: Some(label as i32),
+            r#type: Some(ty as i32),
+            ..Default::default()
+        }
+    }
+
+    fn field_with_options(label: Label, ty: Type, packed: Option<bool>) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            options: Some(FieldOptions {
+                packed,
+                ..Default::default()
+            }),
+            ..field(label, ty)
+        }
+    }
+
+    fn ctx(descriptor: &FieldDescriptorProto, syntax: Syntax) -> FieldWithContext<'_> {
+        FieldWithContext::new(descriptor, syntax, false, false)
+    }
+
+    #[test]
+    fn proto2_optional_field_has_explicit_presence() {
+        let f = field(Label::Optional, Type::Int32);
+        assert!(ctx(&f, Syntax::Proto2).has_explicit_presence());
+    }
+
+    #[test]
+    fn proto2_required_field_has_no_explicit_presence() {
+        let f = field(Label::Required, Type::Int32);
+        let c = ctx(&f, Syntax::Proto2);
+        assert!(!c.has_explicit_presence());
+        assert!(c.is_required());
+    }
+
+    #[test]
+    fn proto2_repeated_field_has_no_explicit_presence() {
+        let f = field(Label::Repeated, Type::Int32);
+        assert!(!ctx(&f, Syntax::Proto2).has_explicit_presence());
+    }
+
+    #[test]
+    fn proto3_plain_singular_field_has_no_explicit_presence() {
+        let f = field(Label::Optional, Type::Int32);
+        assert!(!ctx(&f, Syntax::Proto3).has_explicit_presence());
+    }
+
+    #[test]
+    fn proto3_explicit_optional_field_has_explicit_presence() {
+        let mut f = field(Label::Optional, Type::Int32);
+        f.proto3_optional = Some(true);
+        assert!(ctx(&f, Syntax::Proto3).has_explicit_presence());
+    }
+
+    #[test]
+    fn proto2_repeated_scalar_is_not_packed_by_default() {
+        let f = field(Label::Repeated, Type::Int32);
+        assert!(!ctx(&f, Syntax::Proto2).is_packed());
+    }
+
+    #[test]
+    fn proto2_repeated_scalar_is_packed_when_option_set() {
+        let f = field_with_options(Label::Repeated, Type::Int32, Some(true));
+        assert!(ctx(&f, Syntax::Proto2).is_packed());
+    }
+
+    #[test]
+    fn proto3_repeated_scalar_is_packed_by_default() {
+        let f = field(Label::Repeated, Type::Int32);
+        assert!(ctx(&f, Syntax::Proto3).is_packed());
+    }
+
+    #[test]
+    fn proto3_repeated_scalar_respects_explicit_unpacked() {
+        let f = field_with_options(Label::Repeated, Type::Int32, Some(false));
+        assert!(!ctx(&f, Syntax::Proto3).is_packed());
+    }
+
+    #[test]
+    fn repeated_message_field_is_never_packed() {
+        let f = field(Label::Repeated, Type::Message);
+        assert!(!ctx(&f, Syntax::Proto3).is_packed());
+    }
+
+    #[test]
+    fn singular_field_is_never_packed() {
+        let f = field(Label::Optional, Type::Int32);
+        assert!(!ctx(&f, Syntax::Proto3).is_packed());
+    }
+
+    #[test]
+    fn selector_bits_includes_oneof_and_map_membership() {
+        let f = field(Label::Repeated, Type::Message);
+        let oneof = FieldWithContext::new(&f, Syntax::Proto3, true, false);
+        assert!(FieldFilter::from(FieldSelector::OneofField).matches(&oneof));
+
+        let map = FieldWithContext::new(&f, Syntax::Proto3, false, true);
+        assert!(FieldFilter::from(FieldSelector::MapField).matches(&map));
+
+        let plain = FieldWithContext::new(&f, Syntax::Proto3, false, false);
+        assert!(!FieldFilter::from(FieldSelector::OneofField).matches(&plain));
+        assert!(!FieldFilter::from(FieldSelector::MapField).matches(&plain));
+    }
+
+    #[test]
+    fn message_of_all_scalar_fields_is_copyable() {
+        let fields = [
+            field(Label::Optional, Type::Int32),
+            field(Label::Optional, Type::Bool),
+            field(Label::Required, Type::Fixed64),
+        ];
+        let ctxs: Vec<_> = fields.iter().map(|f| ctx(f, Syntax::Proto2)).collect();
+        assert!(message_is_copyable(ctxs.iter(), &|_| false));
+    }
+
+    #[test]
+    fn message_with_a_message_field_is_not_copyable() {
+        let fields = [field(Label::Optional, Type::Int32), field(Label::Optional, Type::Message)];
+        let ctxs: Vec<_> = fields.iter().map(|f| ctx(f, Syntax::Proto2)).collect();
+        assert!(!message_is_copyable(ctxs.iter(), &|_| false));
+    }
+
+    #[test]
+    fn message_with_a_repeated_scalar_field_is_not_copyable() {
+        let fields = [field(Label::Repeated, Type::Int32)];
+        let ctxs: Vec<_> = fields.iter().map(|f| ctx(f, Syntax::Proto2)).collect();
+        assert!(!message_is_copyable(ctxs.iter(), &|_| false));
+    }
+
+    #[test]
+    fn enum_field_is_copyable_only_if_referenced_enum_is_c_like() {
+        let mut f = field(Label::Optional, Type::Enum);
+        f.type_name = Some(".my.Enum".to_string());
+        let c = ctx(&f, Syntax::Proto2);
+        assert!(c.is_copy_candidate(&|name| name == ".my.Enum"));
+        assert!(!c.is_copy_candidate(&|_| false));
+    }
+
+    #[test]
+    fn substitute_field_type_registers_a_lookupable_override() {
+        let f = field(Label::Optional, Type::Bytes);
+        let mut config = crate::Config::new();
+        config.substitute_field_type(
+            FieldSelector::Bytes,
+            quote::quote!(bytes::Bytes),
+            CodecHint::Bytes,
+        );
+        let c = ctx(&f, Syntax::Proto2);
+        let found = config.field_type_substitutions().lookup(&c).unwrap();
+        assert_eq!(found.codec(), CodecHint::Bytes);
+    }
+
+    fn rendered(attrs: &[syn::Attribute]) -> Vec<String> {
+        attrs
+            .iter()
+            .map(|attr| quote::quote!(#attr).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn type_filter_matches_tests_bit_overlap() {
+        let filter = TypeFilter::from(TypeSelector::ProtobufMessage) | TypeSelector::ProtobufEnum;
+        assert!(filter.matches(TypeFilter::from(TypeSelector::ProtobufMessage)));
+        assert!(!filter.matches(TypeFilter::from(TypeSelector::RustStruct)));
+    }
+
+    #[test]
+    fn customizer_appends_attrs_from_every_matching_type_rule_in_registration_order() {
+        let mut customizer = Customizer::new();
+        customizer.on_type(TypeSelector::ProtobufMessage, |_| {
+            vec![syn::parse_quote!(#[attr_one])]
+        });
+        customizer.on_type(TypeSelector::RustStruct, |_| {
+            vec![syn::parse_quote!(#[attr_two])]
+        });
+
+        let object_bits = TypeFilter::from(TypeSelector::ProtobufMessage) | TypeSelector::RustStruct;
+        let ident = syn::Ident::new("Foo", proc_macro2::Span::call_site());
+        let type_ctx = TypeContext {
+            proto_name: ".my.Foo",
+            rust_ident: &ident,
+            matched: object_bits,
+        };
+
+        let attrs = rendered(&customizer.type_attrs(object_bits, &type_ctx));
+        assert_eq!(attrs.len(), 2);
+        assert!(attrs[0].contains("attr_one"));
+        assert!(attrs[1].contains("attr_two"));
+    }
+
+    #[test]
+    fn customizer_appends_attrs_from_every_matching_field_rule_in_registration_order() {
+        let mut customizer = Customizer::new();
+        customizer.on_field(FieldSelector::Bytes, |_| {
+            vec![syn::parse_quote!(#[attr_one])]
+        });
+        customizer.on_field(FieldSelector::Everything, |_| {
+            vec![syn::parse_quote!(#[attr_two])]
+        });
+
+        let f = field(Label::Optional, Type::Bytes);
+        let field_ctx = ctx(&f, Syntax::Proto2);
+        let ident = syn::Ident::new("foo", proc_macro2::Span::call_site());
+        let rule_ctx = FieldContext {
+            proto_name: "foo",
+            rust_ident: &ident,
+            matched: FieldFilter::from(FieldSelector::Bytes),
+        };
+
+        let attrs = rendered(&customizer.field_attrs(&field_ctx, &rule_ctx));
+        assert_eq!(attrs.len(), 2);
+        assert!(attrs[0].contains("attr_one"));
+        assert!(attrs[1].contains("attr_two"));
+    }
+
+    #[test]
+    fn is_well_known_type_full_matches_known_names() {
+        assert!(is_well_known_type_full(".google.protobuf.Timestamp"));
+        assert!(is_well_known_type_full(".google.protobuf.Any"));
+    }
+
+    #[test]
+    fn is_well_known_type_full_rejects_unknown_names() {
+        assert!(!is_well_known_type_full(".my.Foo"));
+        // missing the leading dot that FieldDescriptorProto::type_name always has
+        assert!(!is_well_known_type_full("google.protobuf.Timestamp"));
+    }
+
+    #[test]
+    fn message_field_targeting_a_well_known_type_sets_the_selector_bit() {
+        let mut f = field(Label::Optional, Type::Message);
+        f.type_name = Some(".google.protobuf.Timestamp".to_string());
+        let c = ctx(&f, Syntax::Proto3);
+        assert!(FieldFilter::from(FieldSelector::WellKnownTypeField).matches(&c));
+    }
+
+    #[test]
+    fn message_field_targeting_a_non_well_known_type_does_not_set_the_selector_bit() {
+        let mut f = field(Label::Optional, Type::Message);
+        f.type_name = Some(".my.Foo".to_string());
+        let c = ctx(&f, Syntax::Proto3);
+        assert!(!FieldFilter::from(FieldSelector::WellKnownTypeField).matches(&c));
+    }
+
+    #[test]
+    fn non_message_field_is_never_a_well_known_type_field_even_with_a_wkt_type_name() {
+        let mut f = field(Label::Optional, Type::Enum);
+        f.type_name = Some(".google.protobuf.Timestamp".to_string());
+        let c = ctx(&f, Syntax::Proto3);
+        assert!(!FieldFilter::from(FieldSelector::WellKnownTypeField).matches(&c));
+    }
+}
@@ -0,0 +1,45 @@
+mod filters;
+
+pub use filters::{
+    CodecHint, Customizer, FieldContext, FieldFilter, FieldSelector, FieldTypeOverride, TypeContext,
+    TypeFilter, TypeSelector,
+};
+
+use filters::FieldTypeSubstitutions;
+use proc_macro2::TokenStream;
+
+/// Configures the code generation process.
+#[derive(Default)]
+pub struct Config {
+    field_type_substitutions: FieldTypeSubstitutions,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `target` as the Rust type for fields matched by `filter`
+    /// (e.g. `bytes::Bytes` for `FieldSelector::Bytes` fields, or a
+    /// zero-copy string type for `FieldSelector::String`), with `codec`
+    /// naming the wire-compatible encode/decode expressions for the new
+    /// type. Filters are consulted in registration order; for a given
+    /// field, the first one registered that matches wins. This only
+    /// records the substitution: no code generator in this crate reads it,
+    /// so it does not change any generated output.
+    pub fn substitute_field_type(
+        &mut self,
+        filter: impl Into<FieldFilter>,
+        target: TokenStream,
+        codec: CodecHint,
+    ) -> &mut Self {
+        self.field_type_substitutions
+            .register(filter.into(), target, codec);
+        self
+    }
+
+    /// The registered field-type substitutions, in registration order.
+    pub(crate) fn field_type_substitutions(&self) -> &FieldTypeSubstitutions {
+        &self.field_type_substitutions
+    }
+}